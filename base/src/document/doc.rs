@@ -3,22 +3,22 @@ use std::collections::HashSet;
 use crate::index::indexable_field::IndexableField;
 
 #[derive(Debug)]
-pub struct Doc {
-    fields: HashSet<IndexableField>,
+pub struct Doc<const D: usize = 1> {
+    fields: HashSet<IndexableField<D>>,
 }
 
-impl Doc {
+impl<const D: usize> Doc<D> {
     pub fn new() -> Self {
         Self {
             fields: HashSet::new(),
         }
     }
 
-    pub fn iter(&self) -> std::collections::hash_set::Iter<IndexableField> {
+    pub fn iter(&self) -> std::collections::hash_set::Iter<IndexableField<D>> {
         self.fields.iter()
     }
 
-    pub fn add(&mut self, field: IndexableField) {
+    pub fn add(&mut self, field: IndexableField<D>) {
         self.fields.insert(field);
     }
 
@@ -14,14 +14,19 @@ use tracing::trace;
 ///
 /// This implementation is kept simple. Here we use a separate Array for the top priority
 /// elements and then we use a smallvec.
-pub(crate) type WeightTy = u8;
+///
+/// `WeightTy` is `u64` so the bitmap genuinely covers all 64 priority slots; callers should pass
+/// the true per-entry RAM usage in bytes as the weight.
+pub(crate) type WeightTy = u64;
 const SIZE: usize = std::mem::size_of::<WeightTy>() * 8;
 
 #[derive(Debug)]
 pub(crate) struct ApproximatePriorityQueue<T> {
     pages: Vec<Option<T>>,
     /// A bitmap to track the taken slot for the first 64 slots. A set bit indicates a taken slot.
-    used_slots: usize,
+    /// Kept as `u64` explicitly (rather than `usize`) so the bitmap is guaranteed to cover all 64
+    /// slots regardless of the target's pointer width.
+    used_slots: u64,
 }
 
 impl<T: PartialEq + Debug> ApproximatePriorityQueue<T> {
@@ -55,7 +60,7 @@ impl<T: PartialEq + Debug> ApproximatePriorityQueue<T> {
                 let destination_slot = destination_slot as usize;
                 // If this is one of the pages with enough weights to be in the first 64 slots, set
                 // the used_slot marker for it and then insert it in its appropriate slot.
-                self.used_slots |= 1usize << destination_slot;
+                self.used_slots |= 1u64 << destination_slot;
                 let old = std::mem::replace(&mut self.pages[destination_slot], Some(entry));
                 assert!(old.is_none(), "Expected None but found {old:?}");
             } else {
@@ -162,6 +167,22 @@ impl<T: PartialEq + Debug> ApproximatePriorityQueue<T> {
     pub(crate) fn is_empty(&self) -> bool {
         self.used_slots == 0
     }
+
+    /// Returns the most-weighty occupied slot without removing it, or `None` if none of the
+    /// first [`SIZE`] slots are occupied.
+    pub(crate) fn peek_highest(&self) -> Option<&T> {
+        let idx = self.used_slots.trailing_zeros() as usize;
+        if idx >= SIZE {
+            None
+        } else {
+            self.pages[idx].as_ref()
+        }
+    }
+
+    /// Convenience wrapper over `poll(|_| true)`: removes and returns the most-weighty element.
+    pub(crate) fn poll_highest(&mut self) -> eyre::Result<Option<T>> {
+        self.poll(|_| true)
+    }
 }
 
 #[cfg(test)]
@@ -231,6 +252,25 @@ mod tests {
         assert_eq!(a.pages.len(), SIZE);
     }
 
+    #[test]
+    fn test_peek_and_poll_highest() {
+        let mut a = ApproximatePriorityQueue::new();
+        assert_eq!(a.peek_highest(), None);
+
+        a.add(2, 2).unwrap();
+        a.add(1, 1).unwrap();
+        a.add(0, 0).unwrap();
+
+        assert_eq!(a.peek_highest(), Some(&2));
+        // peek must not remove the entry.
+        assert_eq!(a.peek_highest(), Some(&2));
+
+        assert_eq!(a.poll_highest().unwrap(), Some(2));
+        assert_eq!(a.poll_highest().unwrap(), Some(1));
+        assert_eq!(a.poll_highest().unwrap(), Some(0));
+        assert_eq!(a.poll_highest().unwrap(), None);
+    }
+
     #[test]
     fn test_predicate() {
         let mut a = ApproximatePriorityQueue::new();
@@ -1,38 +1,118 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
-struct DocumentsWriterPerThreadPool {
-    pages: AtomicU64,
+/// Number of slots tracked by a single bitmap word.
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+/// Default pool size: 16 words, i.e. 1024 concurrent thread states. Chosen to comfortably clear
+/// the old 64-slot ceiling; construct with [`DocumentsWriterPerThreadPool::with_capacity`] for a
+/// different size.
+const DEFAULT_WORDS: usize = 16;
+
+/// Ref: DWPTP. Hands out slots to [`super::documents_writer_flush_control::DocumentWriterFlushControl`].
+///
+/// Backed by a fixed-size `Box<[AtomicU64]>` instead of a single word so the pool is not capped
+/// at 64 concurrent thread states. Each word tracks up to 64 slots; a set bit means the slot is
+/// claimed. The word count is fixed at construction rather than grown on demand: growing the
+/// backing storage behind `&self` would need its own synchronization (e.g. a lock or an
+/// append-only allocator), which would defeat the point of a lock-free claim. Size the pool for
+/// the workload up front via `with_capacity`.
+///
+/// Once every slot is claimed, `claim_free_slot` returns `None` instead of growing; the caller
+/// (`DocumentWriterFlushControl::obtain_and_lock`) is expected to treat that as "no thread state
+/// available right now" and back off until a slot is freed via `unset_bit`, same as Lucene's DWPTP
+/// blocks a thread when the pool is at capacity.
+pub(crate) struct DocumentsWriterPerThreadPool {
+    words: Box<[AtomicU64]>,
 }
 
 impl DocumentsWriterPerThreadPool {
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_WORDS * BITS_PER_WORD)
+    }
+
+    /// Sizes the pool to hold at least `slots` concurrent claims (rounded up to a whole word).
+    pub fn with_capacity(slots: usize) -> Self {
+        let words = slots.div_ceil(BITS_PER_WORD).max(1);
         Self {
-            pages: AtomicU64::new(0),
+            words: (0..words).map(|_| AtomicU64::new(0)).collect(),
         }
     }
+
     /// Sets the bit and returns
     /// true: If the bit was un-set previously and is set after this operation.
     /// false: otherwise.
-    pub fn set_bit(&self, i: u8) -> bool {
-        let bit_i = 1u64 << (i as u64);
-        (self.pages.fetch_or(bit_i, Ordering::AcqRel) & bit_i) == 0
+    pub fn set_bit(&self, i: usize) -> bool {
+        let (word_idx, bit) = Self::locate(i);
+        let bit_i = 1u64 << bit;
+        (self.words[word_idx].fetch_or(bit_i, Ordering::AcqRel) & bit_i) == 0
     }
+
     /// Unset the bit and returns
     /// true: If the bit was set previously and now it is unset.
     /// false: otherwise.
-    pub fn unset_bit(&self, i: u8) -> bool {
-        let bit_i = 1u64 << (i as u64);
-        (self.pages.fetch_and(!bit_i, Ordering::Release) & bit_i) != 0
+    pub fn unset_bit(&self, i: usize) -> bool {
+        let (word_idx, bit) = Self::locate(i);
+        let bit_i = 1u64 << bit;
+        (self.words[word_idx].fetch_and(!bit_i, Ordering::Release) & bit_i) != 0
+    }
+
+    /// Lock-free read of whether slot `i` is currently claimed. Readers only ever take an
+    /// `Ordering::Acquire` load here so they never block a concurrent `claim_free_slot`/`unset_bit`.
+    pub fn is_claimed(&self, i: usize) -> bool {
+        let (word_idx, bit) = Self::locate(i);
+        match self.words.get(word_idx) {
+            Some(word) => (word.load(Ordering::Acquire) & (1u64 << bit)) != 0,
+            None => false,
+        }
+    }
+
+    /// Scans the words for a free bit, then attempts to claim it with a `compare_exchange`,
+    /// retrying on a lost race against another concurrent caller. Returns the global slot index
+    /// `word_idx * 64 + bit`, or `None` if every slot in the pool is claimed. Takes `&self` so
+    /// multiple threads can call this concurrently without an external lock.
+    pub fn claim_free_slot(&self) -> Option<usize> {
+        loop {
+            let mut free = None;
+            for (word_idx, word) in self.words.iter().enumerate() {
+                let current = word.load(Ordering::Acquire);
+                if current != u64::MAX {
+                    free = Some((word_idx, current));
+                    break;
+                }
+            }
+
+            let (word_idx, current) = free?;
+
+            let bit = (!current).trailing_zeros();
+            let claimed = current | (1u64 << bit);
+            match self.words[word_idx].compare_exchange(
+                current,
+                claimed,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(word_idx * BITS_PER_WORD + bit as usize),
+                Err(_) => continue,
+            }
+        }
+    }
+
+    fn locate(i: usize) -> (usize, u32) {
+        (i / BITS_PER_WORD, (i % BITS_PER_WORD) as u32)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::exec::doc_writer_per_thread_pool::DocumentsWriterPerThreadPool;
+    use crate::exec::doc_writer_per_thread_pool::{DocumentsWriterPerThreadPool, BITS_PER_WORD};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
 
     #[test]
     fn test_bool() {
         let bool = DocumentsWriterPerThreadPool::new();
+        bool.claim_free_slot();
 
         assert_eq!(bool.set_bit(5), true);
         assert_eq!(bool.set_bit(5), false);
@@ -43,4 +123,63 @@ mod tests {
 
         assert_eq!(bool.set_bit(63), true);
     }
+
+    #[test]
+    fn claim_free_slot_picks_lowest_free_bit() {
+        let pool = DocumentsWriterPerThreadPool::new();
+
+        assert_eq!(pool.claim_free_slot(), Some(0));
+        assert_eq!(pool.claim_free_slot(), Some(1));
+        assert!(pool.is_claimed(0));
+        assert!(pool.is_claimed(1));
+        assert!(!pool.is_claimed(2));
+
+        assert!(pool.unset_bit(0));
+        assert_eq!(pool.claim_free_slot(), Some(0));
+    }
+
+    #[test]
+    fn claim_free_slot_spans_multiple_words() {
+        // `with_capacity` rounds up to a whole word, so this pool holds two full words.
+        let pool = DocumentsWriterPerThreadPool::with_capacity(BITS_PER_WORD + 1);
+        for expected in 0..(2 * BITS_PER_WORD) {
+            assert_eq!(pool.claim_free_slot(), Some(expected));
+        }
+        assert!(pool.is_claimed(BITS_PER_WORD));
+        assert_eq!(pool.claim_free_slot(), None);
+    }
+
+    #[test]
+    fn claim_free_slot_hands_out_distinct_slots_across_threads() {
+        let pool = Arc::new(DocumentsWriterPerThreadPool::with_capacity(8 * BITS_PER_WORD));
+        let claims_per_thread = 50;
+        let num_threads = 8;
+
+        let handles: Vec<_> = (0..num_threads)
+            .map(|_| {
+                let pool = Arc::clone(&pool);
+                thread::spawn(move || {
+                    (0..claims_per_thread)
+                        .map(|_| pool.claim_free_slot().expect("pool has capacity"))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let seen = Arc::new(AtomicUsize::new(0));
+        let mut all_slots = Vec::new();
+        for handle in handles {
+            all_slots.extend(handle.join().unwrap());
+        }
+        seen.fetch_add(all_slots.len(), Ordering::Relaxed);
+
+        let mut sorted = all_slots.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(
+            sorted.len(),
+            all_slots.len(),
+            "no two threads should have been handed the same slot"
+        );
+    }
 }
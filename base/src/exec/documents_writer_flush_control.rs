@@ -1,9 +1,58 @@
+use super::approx_priority_queue::ApproximatePriorityQueue;
+use super::doc_writer_per_thread_pool::DocumentsWriterPerThreadPool;
+
 /// Ref: DWFC. Controls flushing for the DWPT.
 ///
 /// This is the supplier of the DWPT. When the `DocumentsWriter` needs to index documents, it asks
 /// the DWFC for a page. The DWFC pulls out a page from the PerthreadPool and hands it over.
-pub(crate) struct DocumentWriterFlushControl {}
+///
+/// It also tracks which claimed slots are carrying the most in-RAM bytes, via an
+/// `ApproximatePriorityQueue` keyed by each slot's RAM usage, so that when a flush is needed it
+/// can pick (approximately) the largest in-RAM writer to flush first rather than an arbitrary one.
+pub(crate) struct DocumentWriterFlushControl {
+    pool: DocumentsWriterPerThreadPool,
+    flush_queue: ApproximatePriorityQueue<usize>,
+}
 
 impl DocumentWriterFlushControl {
-    fn obtain_and_lock(&mut self) {}
+    pub(crate) fn new() -> Self {
+        Self {
+            pool: DocumentsWriterPerThreadPool::new(),
+            flush_queue: ApproximatePriorityQueue::new(),
+        }
+    }
+
+    /// Claims a thread-state slot from the pool for the calling `DocumentsWriter` thread. Returns
+    /// `None` if the pool is fully claimed; the caller should back off and retry once a slot is
+    /// freed via `release`.
+    fn obtain_and_lock(&mut self) -> Option<usize> {
+        self.pool.claim_free_slot()
+    }
+
+    /// Returns a slot previously handed out by `obtain_and_lock` back to the pool.
+    fn release(&mut self, slot: usize) {
+        self.pool.unset_bit(slot);
+    }
+
+    /// Records (or updates) how many bytes of RAM `slot`'s thread state is currently holding, so
+    /// it can be considered as a flush candidate.
+    fn update_ram_usage(&mut self, slot: usize, ram_bytes: u64) -> eyre::Result<()> {
+        self.flush_queue.remove(slot);
+        self.flush_queue.add(slot, ram_bytes)
+    }
+
+    /// Returns the slot the queue currently ranks highest by RAM usage, without removing it from
+    /// consideration. The ranking is approximate (by the bit length of each slot's byte count,
+    /// with collisions pushed to lower-ranked positions), so this is not always the slot with the
+    /// most bytes; slots ranked past the queue's 64 priority positions are never returned here.
+    fn peek_flush_candidate(&self) -> Option<&usize> {
+        self.flush_queue.peek_highest()
+    }
+
+    /// Picks the slot the queue currently ranks highest by RAM usage (approximately the one with
+    /// the most bytes, see `peek_flush_candidate`) and removes it from consideration, so its
+    /// thread state can be flushed.
+    fn select_flush_candidate(&mut self) -> eyre::Result<Option<usize>> {
+        self.flush_queue.poll_highest()
+    }
 }
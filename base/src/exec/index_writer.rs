@@ -1,9 +1,25 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use crate::document::{doc::Doc, terms::Term};
+use crate::index::bkd::{BkdTree, Point};
+use crate::index::block_max_wand::{self, Bm25Params, Bm25Scorer, TermCursor};
+use crate::index::postings::PostingsWriter;
+use crate::index::segment::{SegmentReader, SegmentWriter, TermPostings};
 
 use super::documents_writer::DocumentsWriter;
 
 pub struct IndexWriter {
     documents_writer: DocumentsWriter,
+    directory: PathBuf,
+    next_segment_id: u32,
+    /// BKD trees built from the point fields of the most recently flushed segment, keyed by
+    /// field name. Rebuilt wholesale on every `flush`; merging across segments isn't needed yet
+    /// since there's no segment-merge path for postings either.
+    point_indexes: BTreeMap<String, BkdTree<1>>,
+    /// Number of fields each doc in the most recently flushed segment carried, indexed by doc ID.
+    /// Stands in for Lucene's per-doc field length and feeds `Self::search`'s BM25 normalization.
+    doc_lengths: Vec<u32>,
 }
 
 impl IndexWriter {
@@ -19,4 +35,120 @@ impl IndexWriter {
     {
         todo!()
     }
+
+    /// Persists every document buffered in the `DocumentsWriter` as a new, immutable segment
+    /// file (see [`crate::index::segment::SegmentWriter`]) and hands the in-RAM pages back to the
+    /// pool. Field names are indexed as terms, with the postings for a term being the block-
+    /// encoded `(doc_id, freq)` pairs of the documents that carry that field, in insertion order.
+    /// Point field values are also collected into a [`BkdTree`] per field name so they can be
+    /// range-queried via [`Self::range_query`].
+    pub fn flush(&mut self) -> eyre::Result<()> {
+        let docs = self.documents_writer.drain_docs();
+        if docs.is_empty() {
+            return Ok(());
+        }
+
+        let mut postings_by_term: BTreeMap<String, Vec<(u32, u32)>> = BTreeMap::new();
+        let mut points_by_field: BTreeMap<String, Vec<Point<1>>> = BTreeMap::new();
+        let mut doc_lengths = vec![0u32; docs.len()];
+        for (doc_id, doc) in docs.iter().enumerate() {
+            let mut freq_in_doc: BTreeMap<&str, u32> = BTreeMap::new();
+            for field in doc.iter() {
+                *freq_in_doc.entry(field.name()).or_insert(0) += 1;
+                points_by_field
+                    .entry(field.name().to_owned())
+                    .or_default()
+                    .push(Point {
+                        values: *field.value(),
+                        doc_id: doc_id as u32,
+                    });
+            }
+            doc_lengths[doc_id] = freq_in_doc.values().sum();
+            for (name, freq) in freq_in_doc {
+                postings_by_term
+                    .entry(name.to_owned())
+                    .or_default()
+                    .push((doc_id as u32, freq));
+            }
+        }
+        self.doc_lengths = doc_lengths;
+
+        let terms: Vec<TermPostings> = postings_by_term
+            .into_iter()
+            .map(|(term, postings)| {
+                let doc_ids: Vec<u32> = postings.iter().map(|(doc_id, _)| *doc_id).collect();
+                let freqs: Vec<u32> = postings.iter().map(|(_, freq)| *freq).collect();
+                let doc_count = doc_ids.len() as u32;
+                let (bytes, skips) = PostingsWriter::encode(&doc_ids, &freqs);
+                TermPostings {
+                    term,
+                    doc_count,
+                    skips,
+                    postings: bytes.as_slice().to_vec(),
+                }
+            })
+            .collect();
+
+        SegmentWriter::write(&self.segment_path(), &terms)?;
+        self.next_segment_id += 1;
+
+        self.point_indexes = points_by_field
+            .into_iter()
+            .map(|(field, points)| (field, BkdTree::build(points)))
+            .collect();
+
+        Ok(())
+    }
+
+    /// Returns the doc IDs (within the most recently flushed segment) whose `field` value falls
+    /// within the closed range `[lower, upper]`, via the field's [`BkdTree`].
+    pub fn range_query(&self, field: &str, lower: i32, upper: i32) -> Vec<u32> {
+        self.point_indexes
+            .get(field)
+            .map(|tree| tree.intersect([lower], [upper]))
+            .unwrap_or_default()
+    }
+
+    /// Runs a disjunctive top-`k` BM25 query for `terms` (field names) against the most recently
+    /// flushed segment, via Block-Max WAND (see [`crate::index::block_max_wand::top_k`]). Terms
+    /// absent from the segment are skipped rather than failing the whole query. Returns doc IDs
+    /// paired with their score, highest first.
+    pub fn search(&self, terms: &[&str], k: usize) -> eyre::Result<Vec<(u32, f64)>> {
+        if self.next_segment_id == 0 {
+            return Ok(Vec::new());
+        }
+        let reader = SegmentReader::open(&self.last_segment_path())?;
+        let num_docs = self.doc_lengths.len() as u32;
+        let avgdl = if self.doc_lengths.is_empty() {
+            1.0
+        } else {
+            self.doc_lengths.iter().sum::<u32>() as f64 / self.doc_lengths.len() as f64
+        };
+
+        let cursors: Vec<TermCursor> = terms
+            .iter()
+            .filter_map(|term| {
+                let cursor = reader.cursor(term)?;
+                let doc_freq = cursor.doc_count() as u32;
+                let max_freqs = cursor.skip_max_freqs();
+                let scorer = Bm25Scorer::new(num_docs, doc_freq, avgdl, Bm25Params::default());
+                Some(TermCursor::new(cursor, scorer, &max_freqs))
+            })
+            .collect();
+
+        let doc_lengths = self.doc_lengths.clone();
+        Ok(block_max_wand::top_k(cursors, k, move |doc_id| {
+            doc_lengths.get(doc_id as usize).copied().unwrap_or(1)
+        }))
+    }
+
+    fn segment_path(&self) -> PathBuf {
+        self.directory
+            .join(format!("_{}.seg", self.next_segment_id))
+    }
+
+    fn last_segment_path(&self) -> PathBuf {
+        self.directory
+            .join(format!("_{}.seg", self.next_segment_id - 1))
+    }
 }
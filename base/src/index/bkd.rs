@@ -0,0 +1,190 @@
+/// Maximum number of points held in a leaf block before the builder stops splitting.
+pub(crate) const LEAF_SIZE: usize = 512;
+
+/// A `D`-dimensional point paired with the doc ID it was extracted from, the unit the BKD tree
+/// is built and queried over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Point<const D: usize> {
+    pub(crate) values: [i32; D],
+    pub(crate) doc_id: u32,
+}
+
+/// Ref: BKD. A balanced k-d tree over point fields (Lucene's `BKDWriter`/`BKDReader`), built once
+/// at flush time from every `(value, doc_id)` pair indexed for a point field.
+///
+/// Leaves hold up to [`LEAF_SIZE`] raw points; internal nodes store only the dimension and value
+/// they split on plus the min/max bounds of their subtree, so `intersect` can prune whole
+/// subtrees whose bounds don't overlap the query range without visiting a single point.
+pub(crate) struct BkdTree<const D: usize> {
+    root: Node<D>,
+}
+
+enum Node<const D: usize> {
+    Leaf {
+        points: Vec<Point<D>>,
+        min: [i32; D],
+        max: [i32; D],
+    },
+    Internal {
+        split_dim: usize,
+        split_value: i32,
+        min: [i32; D],
+        max: [i32; D],
+        left: Box<Node<D>>,
+        right: Box<Node<D>>,
+    },
+}
+
+impl<const D: usize> BkdTree<D> {
+    /// Builds a balanced tree from `points`, splitting on dimension `depth % D` at each level so
+    /// the split dimension cycles as the tree gets deeper.
+    pub(crate) fn build(mut points: Vec<Point<D>>) -> Self {
+        assert!(!points.is_empty(), "cannot build a BKD tree over no points");
+        let root = Self::build_node(&mut points, 0);
+        Self { root }
+    }
+
+    fn build_node(points: &mut [Point<D>], depth: usize) -> Node<D> {
+        let (min, max) = bounds(points);
+
+        if points.len() <= LEAF_SIZE {
+            let split_dim = depth % D;
+            let mut leaf_points = points.to_vec();
+            leaf_points.sort_by_key(|p| p.values[split_dim]);
+            return Node::Leaf {
+                points: leaf_points,
+                min,
+                max,
+            };
+        }
+
+        let split_dim = depth % D;
+        points.sort_by_key(|p| p.values[split_dim]);
+        let mid = points.len() / 2;
+        let split_value = points[mid].values[split_dim];
+
+        let (left_points, right_points) = points.split_at_mut(mid);
+        let left = Box::new(Self::build_node(left_points, depth + 1));
+        let right = Box::new(Self::build_node(right_points, depth + 1));
+
+        Node::Internal {
+            split_dim,
+            split_value,
+            min,
+            max,
+            left,
+            right,
+        }
+    }
+
+    /// Collects the doc IDs of every point within the closed range `[lower, upper]`, pruning any
+    /// subtree whose bounds are disjoint from the query range and fully accepting any leaf whose
+    /// bounds are fully contained by it, only checking points one-by-one in leaves that partially
+    /// overlap.
+    pub(crate) fn intersect(&self, lower: [i32; D], upper: [i32; D]) -> Vec<u32> {
+        let mut out = Vec::new();
+        Self::intersect_node(&self.root, &lower, &upper, &mut out);
+        out
+    }
+
+    fn intersect_node(node: &Node<D>, lower: &[i32; D], upper: &[i32; D], out: &mut Vec<u32>) {
+        match node {
+            Node::Leaf { points, min, max } => {
+                if is_disjoint(min, max, lower, upper) {
+                    return;
+                }
+                if fully_contains(min, max, lower, upper) {
+                    out.extend(points.iter().map(|p| p.doc_id));
+                    return;
+                }
+                out.extend(points.iter().filter_map(|p| {
+                    (0..D)
+                        .all(|d| p.values[d] >= lower[d] && p.values[d] <= upper[d])
+                        .then_some(p.doc_id)
+                }));
+            }
+            Node::Internal {
+                min, max, left, right, ..
+            } => {
+                if is_disjoint(min, max, lower, upper) {
+                    return;
+                }
+                Self::intersect_node(left, lower, upper, out);
+                Self::intersect_node(right, lower, upper, out);
+            }
+        }
+    }
+}
+
+fn bounds<const D: usize>(points: &[Point<D>]) -> ([i32; D], [i32; D]) {
+    let mut min = points[0].values;
+    let mut max = points[0].values;
+    for p in &points[1..] {
+        for d in 0..D {
+            min[d] = min[d].min(p.values[d]);
+            max[d] = max[d].max(p.values[d]);
+        }
+    }
+    (min, max)
+}
+
+fn is_disjoint<const D: usize>(
+    min: &[i32; D],
+    max: &[i32; D],
+    lower: &[i32; D],
+    upper: &[i32; D],
+) -> bool {
+    (0..D).any(|d| max[d] < lower[d] || min[d] > upper[d])
+}
+
+fn fully_contains<const D: usize>(
+    min: &[i32; D],
+    max: &[i32; D],
+    lower: &[i32; D],
+    upper: &[i32; D],
+) -> bool {
+    (0..D).all(|d| min[d] >= lower[d] && max[d] <= upper[d])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(value: i32, doc_id: u32) -> Point<1> {
+        Point {
+            values: [value],
+            doc_id,
+        }
+    }
+
+    #[test]
+    fn one_dimensional_range_query() {
+        let points: Vec<Point<1>> = (0..1000).map(|i| point(i, i as u32)).collect();
+        let tree = BkdTree::build(points);
+
+        let mut hits = tree.intersect([100], [105]);
+        hits.sort();
+        assert_eq!(hits, vec![100, 101, 102, 103, 104, 105]);
+
+        assert!(tree.intersect([-10], [-1]).is_empty());
+    }
+
+    #[test]
+    fn multi_dimensional_range_query_cycles_split_dimension() {
+        // 1600 points: the root splits on x and its children on y before reaching leaf size.
+        let mut points = Vec::new();
+        for x in 0..40 {
+            for y in 0..40 {
+                points.push(Point {
+                    values: [x, y],
+                    doc_id: (x * 40 + y) as u32,
+                });
+            }
+        }
+        let tree = BkdTree::build(points);
+
+        let mut hits = tree.intersect([5, 5], [6, 6]);
+        hits.sort();
+        assert_eq!(hits, vec![205, 206, 245, 246]);
+    }
+}
@@ -0,0 +1,276 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::index::postings::PostingsCursor;
+
+/// BM25 parameters. Defaults match Lucene's `k1=1.2`, `b=0.75`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Bm25Params {
+    pub(crate) k1: f64,
+    pub(crate) b: f64,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+/// Scores a single term's contribution to a document via BM25:
+/// `idf * tf*(k1+1) / (tf + k1*(1 - b + b*dl/avgdl))`, with
+/// `idf = ln(1 + (N - df + 0.5)/(df + 0.5))`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Bm25Scorer {
+    idf: f64,
+    k1: f64,
+    b: f64,
+    avgdl: f64,
+}
+
+impl Bm25Scorer {
+    pub(crate) fn new(num_docs: u32, doc_freq: u32, avgdl: f64, params: Bm25Params) -> Self {
+        let idf = (1.0 + (num_docs as f64 - doc_freq as f64 + 0.5) / (doc_freq as f64 + 0.5)).ln();
+        Self {
+            idf,
+            k1: params.k1,
+            b: params.b,
+            avgdl,
+        }
+    }
+
+    pub(crate) fn score(&self, tf: u32, dl: u32) -> f64 {
+        let tf = tf as f64;
+        let dl = dl as f64;
+        self.idf * (tf * (self.k1 + 1.0)) / (tf + self.k1 * (1.0 - self.b + self.b * dl / self.avgdl))
+    }
+
+    /// Upper bound on the score any document with term frequency `<= max_tf` can achieve: BM25's
+    /// length-normalization term is smallest (and the score largest) as `dl -> 0`.
+    pub(crate) fn max_score(&self, max_tf: u32) -> f64 {
+        self.score(max_tf, 0)
+    }
+}
+
+/// A term's postings cursor paired with the BM25 scorer needed to turn term frequencies into
+/// scores, plus the term's global upper-bound score cached once at construction.
+pub(crate) struct TermCursor {
+    cursor: PostingsCursor,
+    scorer: Bm25Scorer,
+    upper_bound: f64,
+}
+
+impl TermCursor {
+    pub(crate) fn new(cursor: PostingsCursor, scorer: Bm25Scorer, skips_max_freq: &[u32]) -> Self {
+        let upper_bound = skips_max_freq
+            .iter()
+            .map(|&max_freq| scorer.max_score(max_freq))
+            .fold(0.0, f64::max);
+        Self {
+            cursor,
+            scorer,
+            upper_bound,
+        }
+    }
+
+    fn doc(&self) -> Option<u32> {
+        self.cursor.doc()
+    }
+
+    fn next(&mut self) -> Option<u32> {
+        self.cursor.next()
+    }
+
+    fn advance(&mut self, target: u32) -> Option<u32> {
+        self.cursor.advance(target)
+    }
+
+    /// The cached maximum score achievable by the block the cursor currently sits in.
+    fn block_max_score(&self) -> f64 {
+        self.cursor
+            .block_max_freq()
+            .map(|max_freq| self.scorer.max_score(max_freq))
+            .unwrap_or(0.0)
+    }
+
+    fn score(&self, dl: u32) -> f64 {
+        match self.cursor.freq() {
+            Some(tf) => self.scorer.score(tf, dl),
+            None => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ScoredDoc {
+    score: f64,
+    doc: u32,
+}
+
+impl PartialEq for ScoredDoc {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score && self.doc == other.doc
+    }
+}
+
+impl Eq for ScoredDoc {}
+
+impl PartialOrd for ScoredDoc {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredDoc {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .partial_cmp(&other.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.doc.cmp(&other.doc))
+    }
+}
+
+/// Evaluates a disjunctive top-k query over `terms` using Block-Max WAND, avoiding a full score
+/// computation for documents that cannot make the top-k.
+///
+/// Each iteration sorts the still-active cursors by current doc ID, walks them accumulating
+/// global upper-bound scores until the running sum exceeds the current threshold `theta` (the
+/// smallest score in the top-k heap) to find the *pivot* doc; cursors after it that sit on the
+/// same doc join the pivot group. If the cheaper sum of per-block max scores up to the pivot
+/// can't beat `theta` either, none of those docs can enter the top-k, so the cursors are advanced
+/// past the smallest block boundary (but not past the next cursor's doc) instead of being scored.
+/// Otherwise
+/// all cursors at or before the pivot are aligned on the pivot doc and it is fully scored.
+///
+/// `doc_len` supplies each candidate document's length for BM25 normalization.
+pub(crate) fn top_k<F>(mut terms: Vec<TermCursor>, k: usize, doc_len: F) -> Vec<(u32, f64)>
+where
+    F: Fn(u32) -> u32,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut heap: BinaryHeap<std::cmp::Reverse<ScoredDoc>> = BinaryHeap::with_capacity(k + 1);
+
+    loop {
+        terms.retain(|t| t.doc().is_some());
+        if terms.is_empty() {
+            break;
+        }
+        terms.sort_by_key(|t| t.doc().unwrap());
+
+        let theta = if heap.len() < k {
+            0.0
+        } else {
+            heap.peek().unwrap().0.score
+        };
+
+        let mut running_sum = 0.0;
+        let pivot_idx = terms.iter().position(|t| {
+            running_sum += t.upper_bound;
+            running_sum > theta
+        });
+        let Some(mut pivot_idx) = pivot_idx else {
+            break;
+        };
+        let pivot_doc = terms[pivot_idx].doc().unwrap();
+        while terms.get(pivot_idx + 1).and_then(|t| t.doc()) == Some(pivot_doc) {
+            pivot_idx += 1;
+        }
+
+        let block_max_sum: f64 = terms[..=pivot_idx].iter().map(|t| t.block_max_score()).sum();
+
+        if block_max_sum <= theta {
+            let min_last_doc = terms[..=pivot_idx]
+                .iter()
+                .filter_map(|t| t.cursor.block_last_doc())
+                .min()
+                .unwrap_or(pivot_doc);
+            // Docs at or after the next cursor's doc may hold terms outside this block-max sum.
+            let target = match terms.get(pivot_idx + 1).and_then(|t| t.doc()) {
+                Some(next_doc) => (min_last_doc + 1).min(next_doc),
+                None => min_last_doc + 1,
+            };
+            for t in terms[..=pivot_idx].iter_mut() {
+                t.advance(target);
+            }
+            continue;
+        }
+
+        if terms[0].doc().unwrap() == pivot_doc {
+            let dl = doc_len(pivot_doc);
+            let score: f64 = terms
+                .iter()
+                .take_while(|t| t.doc() == Some(pivot_doc))
+                .map(|t| t.score(dl))
+                .sum();
+
+            if heap.len() < k {
+                heap.push(std::cmp::Reverse(ScoredDoc {
+                    score,
+                    doc: pivot_doc,
+                }));
+            } else if score > heap.peek().unwrap().0.score {
+                heap.pop();
+                heap.push(std::cmp::Reverse(ScoredDoc {
+                    score,
+                    doc: pivot_doc,
+                }));
+            }
+
+            for t in terms.iter_mut() {
+                if t.doc() == Some(pivot_doc) {
+                    t.next();
+                } else {
+                    break;
+                }
+            }
+        } else {
+            terms[0].advance(pivot_doc);
+        }
+    }
+
+    let mut results: Vec<(u32, f64)> = heap.into_iter().map(|r| (r.0.doc, r.0.score)).collect();
+    results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::postings::PostingsWriter;
+
+    fn term_cursor(doc_ids: &[u32], freqs: &[u32], num_docs: u32, avgdl: f64) -> TermCursor {
+        let (bytes, skips) = PostingsWriter::encode(doc_ids, freqs);
+        let max_freqs: Vec<u32> = skips.iter().map(|s| s.max_freq).collect();
+        let scorer = Bm25Scorer::new(num_docs, doc_ids.len() as u32, avgdl, Bm25Params::default());
+        let cursor = PostingsCursor::new(bytes, skips, doc_ids.len());
+        TermCursor::new(cursor, scorer, &max_freqs)
+    }
+
+    #[test]
+    fn single_term_ranks_by_frequency() {
+        let term = term_cursor(&[1, 2, 3, 4], &[1, 5, 2, 1], 10, 50.0);
+        let ranked = top_k(vec![term], 2, |_| 50);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].0, 2);
+    }
+
+    #[test]
+    fn disjunction_prefers_docs_matching_more_terms() {
+        let term_a = term_cursor(&[1, 2, 3], &[2, 2, 2], 10, 20.0);
+        let term_b = term_cursor(&[2, 4], &[2, 2], 10, 20.0);
+
+        let ranked = top_k(vec![term_a, term_b], 3, |_| 20);
+
+        assert_eq!(ranked[0].0, 2);
+        assert_eq!(ranked.len(), 3);
+    }
+
+    #[test]
+    fn k_zero_returns_no_results() {
+        let term = term_cursor(&[1, 2, 3], &[1, 1, 1], 10, 20.0);
+        assert_eq!(top_k(vec![term], 0, |_| 20), Vec::new());
+    }
+}
@@ -1,8 +1,8 @@
 #[derive(Debug, PartialEq, Eq, Hash)]
-pub(crate) enum IndexableField {
+pub(crate) enum IndexableField<const D: usize = 1> {
     IntPoint {
         name: String,
-        value: i32,
+        value: [i32; D],
         props: Vec<FieldProperties>,
     },
     //IntRange(CommonParams),
@@ -14,15 +14,21 @@ pub(crate) enum FieldProperties {
     Tokenized,
 }
 
-impl IndexableField {
-    pub(crate) fn new_int(name: &str, value: i32) -> Self {
+impl<const D: usize> IndexableField<D> {
+    /// A `D`-dimensional point field, e.g. `[i32; 2]` for a lat/lon pair.
+    pub(crate) fn new_point(name: &str, value: [i32; D]) -> Self {
         IndexableField::IntPoint {
             name: name.to_owned(),
             value,
             props: vec![],
         }
     }
-    pub(crate) fn new_int_with_props(name: &str, value: i32, props: Vec<FieldProperties>) -> Self {
+
+    pub(crate) fn new_point_with_props(
+        name: &str,
+        value: [i32; D],
+        props: Vec<FieldProperties>,
+    ) -> Self {
         IndexableField::IntPoint {
             name: name.to_owned(),
             value,
@@ -37,4 +43,20 @@ impl IndexableField {
             //IndexableField::Text(p) => &p.name,
         }
     }
+
+    pub(crate) fn value(&self) -> &[i32; D] {
+        match self {
+            IndexableField::IntPoint { value, .. } => value,
+        }
+    }
+}
+
+impl IndexableField<1> {
+    pub(crate) fn new_int(name: &str, value: i32) -> Self {
+        IndexableField::new_point(name, [value])
+    }
+
+    pub(crate) fn new_int_with_props(name: &str, value: i32, props: Vec<FieldProperties>) -> Self {
+        IndexableField::new_point_with_props(name, [value], props)
+    }
 }
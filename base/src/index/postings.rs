@@ -0,0 +1,449 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Number of postings entries packed into a single frame-of-reference block. The trailing,
+/// possibly-short block at the end of a term's postings list falls back to VInt encoding.
+pub(crate) const BLOCK_SIZE: usize = 128;
+
+/// A cheaply-cloneable, immutable view over a byte buffer backing a decoded postings block.
+///
+/// Stands in for the mmap-backed buffer a real segment reader would hand out; kept as an owned
+/// `Arc<[u8]>` plus a `Range` here since there is no mmap dependency wired up yet. [`Self::slice`]
+/// carves out a sub-range without copying, the way a segment reader slices a single term's
+/// postings out of a file holding many terms back-to-back.
+#[derive(Debug, Clone)]
+pub(crate) struct OwnedBytes {
+    data: Arc<[u8]>,
+    range: Range<usize>,
+}
+
+impl OwnedBytes {
+    pub(crate) fn new(data: Vec<u8>) -> Self {
+        let len = data.len();
+        Self {
+            data: data.into(),
+            range: 0..len,
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.data[self.range.clone()]
+    }
+
+    /// Returns a new `OwnedBytes` over `range`, interpreted relative to this slice (not the
+    /// underlying buffer), sharing the same backing allocation.
+    pub(crate) fn slice(&self, range: Range<usize>) -> OwnedBytes {
+        let start = self.range.start + range.start;
+        let end = self.range.start + range.end;
+        assert!(end <= self.range.end, "slice out of bounds");
+        OwnedBytes {
+            data: Arc::clone(&self.data),
+            range: start..end,
+        }
+    }
+}
+
+/// One entry in a term's skip list: the last doc ID contained in a block, the byte offset into
+/// the postings payload where that block's encoded data begins, and the block's maximum term
+/// frequency (used by Block-Max WAND to derive a per-block score upper bound without decoding).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SkipEntry {
+    pub(crate) last_doc: u32,
+    pub(crate) offset: u32,
+    pub(crate) max_freq: u32,
+}
+
+/// Encodes a single term's postings (ascending doc IDs plus per-doc term frequencies) into a
+/// block-compressed byte stream, modeled on Lucene's block postings format.
+///
+/// Full [`BLOCK_SIZE`]-entry blocks are delta-encoded (gaps between consecutive doc IDs) and
+/// frame-of-reference bit-packed at the block's maximum bit width: a one-byte `num_bits` header
+/// followed by the packed gaps, then the same for frequencies. The trailing partial block is
+/// VInt-encoded instead. A skip list recording each block's last doc ID and byte offset is built
+/// alongside so a [`PostingsCursor`] can jump straight to the block containing a target doc.
+pub(crate) struct PostingsWriter;
+
+impl PostingsWriter {
+    pub(crate) fn encode(doc_ids: &[u32], freqs: &[u32]) -> (OwnedBytes, Vec<SkipEntry>) {
+        assert_eq!(
+            doc_ids.len(),
+            freqs.len(),
+            "doc_ids and freqs must be the same length"
+        );
+
+        let mut payload = Vec::new();
+        let mut skips = Vec::new();
+        let mut prev_doc = 0u32;
+
+        for block_start in (0..doc_ids.len()).step_by(BLOCK_SIZE) {
+            let block_end = (block_start + BLOCK_SIZE).min(doc_ids.len());
+            let block_ids = &doc_ids[block_start..block_end];
+            let block_freqs = &freqs[block_start..block_end];
+            let offset = payload.len() as u32;
+
+            if block_ids.len() == BLOCK_SIZE {
+                let gaps: Vec<u32> = block_ids
+                    .iter()
+                    .map(|&id| {
+                        let gap = id - prev_doc;
+                        prev_doc = id;
+                        gap
+                    })
+                    .collect();
+
+                let doc_bits = bits_needed(gaps.iter().copied().max().unwrap_or(0));
+                payload.push(doc_bits);
+                pack_bits(&gaps, doc_bits, &mut payload);
+
+                let freq_bits = bits_needed(block_freqs.iter().copied().max().unwrap_or(0));
+                payload.push(freq_bits);
+                pack_bits(block_freqs, freq_bits, &mut payload);
+            } else {
+                for (&id, &freq) in block_ids.iter().zip(block_freqs) {
+                    let gap = id - prev_doc;
+                    prev_doc = id;
+                    write_vint(gap, &mut payload);
+                    write_vint(freq, &mut payload);
+                }
+            }
+
+            skips.push(SkipEntry {
+                last_doc: *block_ids.last().unwrap(),
+                offset,
+                max_freq: block_freqs.iter().copied().max().unwrap_or(0),
+            });
+        }
+
+        (OwnedBytes::new(payload), skips)
+    }
+}
+
+/// Reads back a [`PostingsWriter::encode`]d stream, exposing a forward-only cursor over
+/// `(doc, freq)` pairs. `advance` binary-searches the skip list to jump directly to the block
+/// that can contain the target doc before prefix-summing gaps back into absolute doc IDs.
+pub(crate) struct PostingsCursor {
+    data: OwnedBytes,
+    skips: Vec<SkipEntry>,
+    total_count: usize,
+    block_idx: usize,
+    block_doc_ids: Vec<u32>,
+    block_freqs: Vec<u32>,
+    pos_in_block: usize,
+    exhausted: bool,
+}
+
+impl PostingsCursor {
+    pub(crate) fn new(data: OwnedBytes, skips: Vec<SkipEntry>, total_count: usize) -> Self {
+        let exhausted = total_count == 0;
+        let mut cursor = Self {
+            data,
+            skips,
+            total_count,
+            block_idx: 0,
+            block_doc_ids: Vec::new(),
+            block_freqs: Vec::new(),
+            pos_in_block: 0,
+            exhausted,
+        };
+        if !cursor.exhausted {
+            cursor.decode_block(0);
+        }
+        cursor
+    }
+
+    pub(crate) fn doc(&self) -> Option<u32> {
+        if self.exhausted {
+            None
+        } else {
+            self.block_doc_ids.get(self.pos_in_block).copied()
+        }
+    }
+
+    pub(crate) fn freq(&self) -> Option<u32> {
+        if self.exhausted {
+            None
+        } else {
+            self.block_freqs.get(self.pos_in_block).copied()
+        }
+    }
+
+    /// The maximum term frequency in the block the cursor is currently positioned in, read
+    /// straight from the skip list without decoding the block.
+    pub(crate) fn block_max_freq(&self) -> Option<u32> {
+        if self.exhausted {
+            None
+        } else {
+            self.skips.get(self.block_idx).map(|s| s.max_freq)
+        }
+    }
+
+    /// The total number of postings (i.e. the term's document frequency) this cursor was built
+    /// from, needed by a scorer to compute IDF.
+    pub(crate) fn doc_count(&self) -> usize {
+        self.total_count
+    }
+
+    /// The per-block maximum term frequencies across the whole postings list, in block order.
+    /// Used to seed a [`crate::index::block_max_wand::TermCursor`]'s cached upper-bound score.
+    pub(crate) fn skip_max_freqs(&self) -> Vec<u32> {
+        self.skips.iter().map(|s| s.max_freq).collect()
+    }
+
+    /// The last doc ID contained in the block the cursor is currently positioned in.
+    pub(crate) fn block_last_doc(&self) -> Option<u32> {
+        if self.exhausted {
+            None
+        } else {
+            self.skips.get(self.block_idx).map(|s| s.last_doc)
+        }
+    }
+
+    pub(crate) fn next(&mut self) -> Option<u32> {
+        if self.exhausted {
+            return None;
+        }
+        self.pos_in_block += 1;
+        if self.pos_in_block >= self.block_doc_ids.len() {
+            if self.block_idx + 1 >= self.skips.len() {
+                self.exhausted = true;
+                return None;
+            }
+            self.block_idx += 1;
+            self.decode_block(self.block_idx);
+        }
+        self.doc()
+    }
+
+    /// Advances to the first doc ID `>= target`, or exhausts the cursor if none exists.
+    pub(crate) fn advance(&mut self, target: u32) -> Option<u32> {
+        if self.exhausted {
+            return None;
+        }
+        if let Some(current) = self.doc() {
+            if current >= target {
+                return Some(current);
+            }
+        }
+
+        // Skip entries' `last_doc`s are ascending, so the first block that can hold `target` is
+        // found by binary search.
+        let remaining = &self.skips[self.block_idx..];
+        let skip_ahead = remaining.partition_point(|e| e.last_doc < target);
+        if skip_ahead == remaining.len() {
+            self.exhausted = true;
+            return None;
+        }
+        let target_block = self.block_idx + skip_ahead;
+
+        if target_block != self.block_idx {
+            self.block_idx = target_block;
+            self.decode_block(self.block_idx);
+        }
+
+        while let Some(doc) = self.doc() {
+            if doc >= target {
+                return Some(doc);
+            }
+            self.pos_in_block += 1;
+        }
+        self.exhausted = true;
+        None
+    }
+
+    fn decode_block(&mut self, block_idx: usize) {
+        let prev_doc = if block_idx == 0 {
+            0
+        } else {
+            self.skips[block_idx - 1].last_doc
+        };
+        let offset = self.skips[block_idx].offset as usize;
+        let block_len = if (block_idx + 1) * BLOCK_SIZE <= self.total_count {
+            BLOCK_SIZE
+        } else {
+            self.total_count - block_idx * BLOCK_SIZE
+        };
+
+        let bytes = self.data.as_slice();
+        self.pos_in_block = 0;
+
+        if block_len == BLOCK_SIZE {
+            let mut pos = offset;
+            let doc_bits = bytes[pos];
+            pos += 1;
+            let packed_len = packed_bytes_len(doc_bits, BLOCK_SIZE);
+            let gaps = unpack_bits(&bytes[pos..pos + packed_len], doc_bits, BLOCK_SIZE);
+            pos += packed_len;
+
+            let freq_bits = bytes[pos];
+            pos += 1;
+            let packed_len = packed_bytes_len(freq_bits, BLOCK_SIZE);
+            let freqs = unpack_bits(&bytes[pos..pos + packed_len], freq_bits, BLOCK_SIZE);
+
+            let mut prev = prev_doc;
+            self.block_doc_ids = gaps
+                .into_iter()
+                .map(|gap| {
+                    prev += gap;
+                    prev
+                })
+                .collect();
+            self.block_freqs = freqs;
+        } else {
+            let mut pos = offset;
+            let mut prev = prev_doc;
+            let mut doc_ids = Vec::with_capacity(block_len);
+            let mut freqs = Vec::with_capacity(block_len);
+            for _ in 0..block_len {
+                let gap = read_vint(bytes, &mut pos);
+                let freq = read_vint(bytes, &mut pos);
+                prev += gap;
+                doc_ids.push(prev);
+                freqs.push(freq);
+            }
+            self.block_doc_ids = doc_ids;
+            self.block_freqs = freqs;
+        }
+    }
+}
+
+/// Minimum number of bits needed to represent `max_value` (0 bits for an all-zero block).
+fn bits_needed(max_value: u32) -> u8 {
+    (32 - max_value.leading_zeros()) as u8
+}
+
+fn packed_bytes_len(num_bits: u8, count: usize) -> usize {
+    (num_bits as usize * count).div_ceil(8)
+}
+
+/// Frame-of-reference bit-packs `values` at a uniform `num_bits` width, LSB-first.
+fn pack_bits(values: &[u32], num_bits: u8, out: &mut Vec<u8>) {
+    if num_bits == 0 {
+        return;
+    }
+    let mut bit_buf: u64 = 0;
+    let mut bit_len: u32 = 0;
+    for &value in values {
+        bit_buf |= (value as u64) << bit_len;
+        bit_len += num_bits as u32;
+        while bit_len >= 8 {
+            out.push((bit_buf & 0xFF) as u8);
+            bit_buf >>= 8;
+            bit_len -= 8;
+        }
+    }
+    if bit_len > 0 {
+        out.push((bit_buf & 0xFF) as u8);
+    }
+}
+
+fn unpack_bits(data: &[u8], num_bits: u8, count: usize) -> Vec<u32> {
+    if num_bits == 0 {
+        return vec![0; count];
+    }
+    let mut values = Vec::with_capacity(count);
+    let mut bit_buf: u64 = 0;
+    let mut bit_len: u32 = 0;
+    let mut byte_pos = 0;
+    let mask = (1u64 << num_bits) - 1;
+
+    for _ in 0..count {
+        while bit_len < num_bits as u32 {
+            bit_buf |= (data[byte_pos] as u64) << bit_len;
+            bit_len += 8;
+            byte_pos += 1;
+        }
+        values.push((bit_buf & mask) as u32);
+        bit_buf >>= num_bits;
+        bit_len -= num_bits as u32;
+    }
+    values
+}
+
+/// Writes `value` as a Lucene-style VInt: 7 bits of payload per byte, high bit set while more
+/// bytes follow.
+fn write_vint(mut value: u32, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value != 0 {
+            out.push(byte | 0x80);
+        } else {
+            out.push(byte);
+            break;
+        }
+    }
+}
+
+fn read_vint(data: &[u8], pos: &mut usize) -> u32 {
+    let mut result = 0u32;
+    let mut shift = 0u32;
+    loop {
+        let byte = data[*pos];
+        *pos += 1;
+        result |= ((byte & 0x7F) as u32) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vint_roundtrip() {
+        for value in [0u32, 1, 127, 128, 300, u32::MAX] {
+            let mut buf = Vec::new();
+            write_vint(value, &mut buf);
+            let mut pos = 0;
+            assert_eq!(read_vint(&buf, &mut pos), value);
+            assert_eq!(pos, buf.len());
+        }
+    }
+
+    #[test]
+    fn pack_unpack_roundtrip() {
+        let values: Vec<u32> = (0..BLOCK_SIZE as u32).map(|i| i * 3 % 500).collect();
+        let num_bits = bits_needed(*values.iter().max().unwrap());
+        let mut packed = Vec::new();
+        pack_bits(&values, num_bits, &mut packed);
+        let unpacked = unpack_bits(&packed, num_bits, values.len());
+        assert_eq!(values, unpacked);
+    }
+
+    #[test]
+    fn full_block_cursor_walks_and_advances() {
+        let doc_ids: Vec<u32> = (0..BLOCK_SIZE as u32).map(|i| i * 2).collect();
+        let freqs: Vec<u32> = (0..BLOCK_SIZE as u32).map(|i| i % 7 + 1).collect();
+        let (bytes, skips) = PostingsWriter::encode(&doc_ids, &freqs);
+        assert_eq!(skips.len(), 1);
+        assert_eq!(skips[0].last_doc, doc_ids[BLOCK_SIZE - 1]);
+
+        let mut cursor = PostingsCursor::new(bytes, skips, doc_ids.len());
+        assert_eq!(cursor.doc(), Some(0));
+        assert_eq!(cursor.freq(), Some(1));
+
+        assert_eq!(cursor.advance(51), Some(52));
+        assert_eq!(cursor.freq(), freqs.get(26).copied());
+
+        for _ in 0..(BLOCK_SIZE - 26) {
+            cursor.next();
+        }
+        assert_eq!(cursor.doc(), None);
+    }
+
+    #[test]
+    fn partial_trailing_block_uses_vint() {
+        let doc_ids: Vec<u32> = (0..BLOCK_SIZE as u32 + 10).collect();
+        let freqs: Vec<u32> = vec![1; doc_ids.len()];
+        let (bytes, skips) = PostingsWriter::encode(&doc_ids, &freqs);
+        assert_eq!(skips.len(), 2);
+
+        let mut cursor = PostingsCursor::new(bytes, skips, doc_ids.len());
+        assert_eq!(cursor.advance(BLOCK_SIZE as u32 + 5), Some(BLOCK_SIZE as u32 + 5));
+        assert_eq!(cursor.next(), Some(BLOCK_SIZE as u32 + 6));
+    }
+}
@@ -0,0 +1,280 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::index::postings::{OwnedBytes, PostingsCursor, SkipEntry};
+
+/// Magic number identifying a segment file: ASCII "LCRS" (LuCeneRuSt) read as a big-endian
+/// `u32`; like every header field it is stored little-endian, so the file starts with "SRCL".
+const MAGIC: u32 = 0x4C43_5253;
+const FORMAT_VERSION: u32 = 1;
+/// Target fraction of buckets occupied before the table is considered full; chosen the way
+/// Lucene's own hash structures pick a load factor to keep probe chains short.
+const LOAD_FACTOR: f64 = 0.8;
+/// `(term_hash: u64, block_offset: u64)`.
+const BUCKET_SLOT_SIZE: usize = 16;
+const HEADER_SIZE: usize = 4 + 4 + 4 + 8;
+/// `(last_doc: u32, offset: u32, max_freq: u32)`, the on-disk form of [`SkipEntry`].
+const SKIP_ENTRY_SIZE: usize = 12;
+
+/// A term's block-encoded postings plus the metadata (`doc_count`, skip list) a reader needs to
+/// reconstruct a [`PostingsCursor`] from them, without re-running `PostingsWriter::encode`.
+pub(crate) struct TermPostings {
+    pub(crate) term: String,
+    pub(crate) doc_count: u32,
+    pub(crate) skips: Vec<SkipEntry>,
+    pub(crate) postings: Vec<u8>,
+}
+
+/// Ref: a minimal segment file. Lays out a fixed header, then an open-addressed hash table
+/// mapping terms to a metadata block, then the metadata blocks and their postings bytes packed
+/// back-to-back, so the whole thing can be read back with a single sequential read (or mmap'd,
+/// once the crate grows that dependency) and probed without touching any postings until a term is
+/// known to exist.
+///
+/// Layout: `[header][bucket table][doc_count, skip list, postings]*`, one metadata-plus-postings
+/// block per term, in the order `terms` was given.
+pub(crate) struct SegmentWriter;
+
+impl SegmentWriter {
+    pub(crate) fn write(path: &Path, terms: &[TermPostings]) -> eyre::Result<()> {
+        let bucket_count = next_bucket_count(terms.len());
+        let mut buckets = vec![(0u64, 0u64); bucket_count];
+
+        let mut postings_blob = Vec::new();
+        for term in terms {
+            let block_offset = postings_blob.len() as u64;
+
+            postings_blob.extend_from_slice(&term.doc_count.to_le_bytes());
+            postings_blob.extend_from_slice(&(term.skips.len() as u32).to_le_bytes());
+            for skip in &term.skips {
+                postings_blob.extend_from_slice(&skip.last_doc.to_le_bytes());
+                postings_blob.extend_from_slice(&skip.offset.to_le_bytes());
+                postings_blob.extend_from_slice(&skip.max_freq.to_le_bytes());
+            }
+            postings_blob.extend_from_slice(&(term.postings.len() as u32).to_le_bytes());
+            postings_blob.extend_from_slice(&term.postings);
+
+            let hash = hash_term(&term.term);
+            let mut slot = (hash & (bucket_count as u64 - 1)) as usize;
+            loop {
+                if buckets[slot].0 == 0 {
+                    buckets[slot] = (hash, block_offset);
+                    break;
+                }
+                slot = (slot + 1) % bucket_count;
+            }
+        }
+
+        let postings_offset = (HEADER_SIZE + bucket_count * BUCKET_SLOT_SIZE) as u64;
+
+        let mut out = Vec::with_capacity(postings_offset as usize + postings_blob.len());
+        out.extend_from_slice(&MAGIC.to_le_bytes());
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&(bucket_count as u32).to_le_bytes());
+        out.extend_from_slice(&postings_offset.to_le_bytes());
+        for (hash, offset) in &buckets {
+            out.extend_from_slice(&hash.to_le_bytes());
+            out.extend_from_slice(&offset.to_le_bytes());
+        }
+        out.extend_from_slice(&postings_blob);
+
+        let mut file = File::create(path)?;
+        file.write_all(&out)?;
+        Ok(())
+    }
+}
+
+/// Reads a segment file written by [`SegmentWriter`] and resolves terms to a [`PostingsCursor`]
+/// by hashing and linearly probing the bucket table, never touching a term's metadata or
+/// postings until a match (or the empty-slot sentinel) is found.
+pub(crate) struct SegmentReader {
+    data: OwnedBytes,
+    bucket_count: usize,
+    postings_offset: u64,
+}
+
+impl SegmentReader {
+    pub(crate) fn open(path: &Path) -> eyre::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Self::from_bytes(bytes)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> eyre::Result<Self> {
+        if bytes.len() < HEADER_SIZE {
+            eyre::bail!("segment file is smaller than its header");
+        }
+        let magic = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
+        if magic != MAGIC {
+            eyre::bail!("bad segment magic: {magic:#x}");
+        }
+        let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        if version != FORMAT_VERSION {
+            eyre::bail!("unsupported segment format version: {version}");
+        }
+        let bucket_count = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let postings_offset = u64::from_le_bytes(bytes[12..20].try_into().unwrap());
+
+        Ok(Self {
+            data: OwnedBytes::new(bytes),
+            bucket_count,
+            postings_offset,
+        })
+    }
+
+    /// Returns the byte offset, relative to the postings region, of `term`'s metadata block.
+    fn lookup(&self, term: &str) -> Option<u64> {
+        let hash = hash_term(term);
+        let mut slot = (hash & (self.bucket_count as u64 - 1)) as usize;
+        let bytes = self.data.as_slice();
+
+        for _ in 0..self.bucket_count {
+            let slot_start = HEADER_SIZE + slot * BUCKET_SLOT_SIZE;
+            let slot_hash = u64::from_le_bytes(bytes[slot_start..slot_start + 8].try_into().unwrap());
+            if slot_hash == 0 {
+                return None;
+            }
+            if slot_hash == hash {
+                let offset =
+                    u64::from_le_bytes(bytes[slot_start + 8..slot_start + 16].try_into().unwrap());
+                return Some(offset);
+            }
+            slot = (slot + 1) % self.bucket_count;
+        }
+        None
+    }
+
+    /// Looks up `term` and, if present, decodes its `doc_count` and skip list and hands back a
+    /// ready-to-use [`PostingsCursor`] over its postings bytes (sliced out of the segment's
+    /// backing buffer with no copy, via [`OwnedBytes::slice`]).
+    pub(crate) fn cursor(&self, term: &str) -> Option<PostingsCursor> {
+        let block_offset = self.lookup(term)?;
+        let start = (self.postings_offset + block_offset) as usize;
+        let bytes = self.data.as_slice();
+
+        let doc_count = u32::from_le_bytes(bytes[start..start + 4].try_into().unwrap());
+        let skip_count = u32::from_le_bytes(bytes[start + 4..start + 8].try_into().unwrap()) as usize;
+
+        let mut pos = start + 8;
+        let mut skips = Vec::with_capacity(skip_count);
+        for _ in 0..skip_count {
+            let last_doc = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap());
+            let offset = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap());
+            let max_freq = u32::from_le_bytes(bytes[pos + 8..pos + 12].try_into().unwrap());
+            skips.push(SkipEntry {
+                last_doc,
+                offset,
+                max_freq,
+            });
+            pos += SKIP_ENTRY_SIZE;
+        }
+
+        let postings_len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+        pos += 4;
+
+        let postings = self.data.slice(pos..pos + postings_len);
+        Some(PostingsCursor::new(postings, skips, doc_count as usize))
+    }
+}
+
+/// Next power of two above `entries / LOAD_FACTOR`, with a floor of 1 so an empty segment still
+/// gets a (degenerate) table.
+fn next_bucket_count(entries: usize) -> usize {
+    let needed = (entries as f64 / LOAD_FACTOR).ceil() as usize;
+    needed.max(1).next_power_of_two()
+}
+
+/// FNV-1a is used instead of `std`'s `DefaultHasher` because the latter is seeded randomly per
+/// process: a persisted term index needs a hash that reproduces identically across writer and
+/// reader runs. Zero is reserved as the empty-slot sentinel.
+fn hash_term(term: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for byte in term.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    if hash == 0 {
+        1
+    } else {
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::postings::PostingsWriter;
+
+    fn term_postings(term: &str, doc_ids: &[u32], freqs: &[u32]) -> TermPostings {
+        let (bytes, skips) = PostingsWriter::encode(doc_ids, freqs);
+        TermPostings {
+            term: term.to_string(),
+            doc_count: doc_ids.len() as u32,
+            skips,
+            postings: bytes.as_slice().to_vec(),
+        }
+    }
+
+    #[test]
+    fn bucket_count_grows_with_load_factor() {
+        assert_eq!(next_bucket_count(0), 1);
+        assert_eq!(next_bucket_count(1), 2);
+        assert_eq!(next_bucket_count(10), 16);
+    }
+
+    #[test]
+    fn write_then_read_reconstructs_a_working_cursor() {
+        let dir = std::env::temp_dir().join(format!(
+            "lucene_in_rust_segment_test_{}",
+            hash_term("write_then_read_reconstructs_a_working_cursor")
+        ));
+        let terms = vec![
+            term_postings("alpha", &[1, 2, 3], &[1, 1, 1]),
+            term_postings("beta", &[4, 5], &[2, 3]),
+            term_postings("gamma", &[6], &[1]),
+        ];
+
+        SegmentWriter::write(&dir, &terms).unwrap();
+        let reader = SegmentReader::open(&dir).unwrap();
+
+        let mut cursor = reader.cursor("beta").expect("beta should be present");
+        assert_eq!(cursor.doc(), Some(4));
+        assert_eq!(cursor.freq(), Some(2));
+        assert_eq!(cursor.next(), Some(5));
+        assert_eq!(cursor.freq(), Some(3));
+        assert_eq!(cursor.next(), None);
+
+        assert!(reader.cursor("missing").is_none());
+
+        std::fs::remove_file(&dir).unwrap();
+    }
+
+    #[test]
+    fn cursor_survives_a_block_boundary() {
+        let dir = std::env::temp_dir().join(format!(
+            "lucene_in_rust_segment_test_{}",
+            hash_term("cursor_survives_a_block_boundary")
+        ));
+        let doc_ids: Vec<u32> = (0..300).collect();
+        let freqs: Vec<u32> = vec![1; doc_ids.len()];
+        let terms = vec![term_postings("big", &doc_ids, &freqs)];
+
+        SegmentWriter::write(&dir, &terms).unwrap();
+        let reader = SegmentReader::open(&dir).unwrap();
+
+        let mut cursor = reader.cursor("big").unwrap();
+        assert_eq!(cursor.advance(290), Some(290));
+        let mut count = 1;
+        while cursor.next().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 10);
+
+        std::fs::remove_file(&dir).unwrap();
+    }
+}